@@ -0,0 +1,198 @@
+//! Procedural macros backing `monoio::join!` and `monoio::try_join!`.
+//!
+//! The public `join!`/`try_join!` macros in `monoio` used to be implemented
+//! as `macro_rules!` tt-munchers: expanding N branches required a recursive
+//! normalization pass (`@{ ($($s)*) ... }`), which is quadratic in the
+//! number of branches and runs into the macro recursion limit for large
+//! joins. These proc macros instead parse the branch list once with `syn`
+//! and emit a flat `match` over branch indices, so `join!`'s expansion is
+//! linear in the branch count and there is a single code path that can
+//! bias/rotate which branch is polled first. `try_join!`'s expansion is not
+//! linear: each branch's `Err` arm also resets every sibling branch (see
+//! Cancellation below), so it grows with the square of the branch count.
+//!
+//! These macros are not meant to be used directly; `monoio::join!` and
+//! `monoio::try_join!` call into them and are the supported public API.
+//!
+//! ### Fairness
+//!
+//! The generated `poll_fn` body rotates which branch is polled first from
+//! call to call (`rotate % branches`, guarded against the zero-branch case
+//! so polling a degenerate `join!()`/`try_join!()` does not panic), so a branch
+//! that is always ready (for example, one wrapping a hot channel) cannot
+//! repeatedly starve the others. The output tuple is still assembled in
+//! the branches' original declaration order, regardless of polling order.
+//!
+//! ### Cancellation
+//!
+//! Because monoio is an io_uring runtime, `try_join!` additionally cancels
+//! the sibling branches' in-flight operations as soon as one branch returns
+//! `Err`, rather than leaving that to whenever the whole combinator is
+//! eventually dropped; see [`try_join_impl`] for the caller requirements
+//! this relies on. `monoio::try_join_all`, the dynamic-collection
+//! counterpart to `try_join!`, applies this same rule even though it is a
+//! plain combinator rather than one of these generated macros.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse::Parser, punctuated::Punctuated, Expr, Index, Token};
+
+fn parse_branches(input: TokenStream) -> syn::Result<Punctuated<Expr, Token![,]>> {
+    Punctuated::<Expr, Token![,]>::parse_terminated.parse(input)
+}
+
+/// Shared codegen for `join!`/`try_join!`. `try_mode` selects whether a
+/// branch's output is treated as a `Result` that can short-circuit the whole
+/// combinator.
+fn expand(input: TokenStream, try_mode: bool) -> TokenStream2 {
+    let branches = match parse_branches(input) {
+        Ok(branches) => branches,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    let count = branches.len();
+    let futs: Vec<_> = (0..count).map(|i| format_ident!("__fut_{}", i)).collect();
+
+    let inits = branches.iter().zip(&futs).map(|(e, fut)| {
+        quote! { let mut #fut = maybe_done(#e); }
+    });
+
+    let pins = futs.iter().map(|fut| {
+        // Safety: `#fut` is stored in this function's stack frame (captured
+        // by the `poll_fn` closure below) and is never moved out of.
+        quote! { let mut #fut = unsafe { Pin::new_unchecked(&mut #fut) }; }
+    });
+
+    let poll_arms = futs.iter().enumerate().map(|(i, fut)| {
+        let idx = Index::from(i);
+        if try_mode {
+            let siblings: Vec<_> = futs
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, f)| f)
+                .collect();
+            quote! {
+                #idx => {
+                    if #fut.as_mut().poll(cx).is_pending() {
+                        is_pending = true;
+                    } else if #fut.as_mut().output_mut().expect("expected completed future").is_err() {
+                        let __err = #fut
+                            .as_mut()
+                            .take_output()
+                            .expect("expected completed future")
+                            .err()
+                            .unwrap();
+                        // Eagerly cancel the other branches instead of
+                        // leaving them to be dropped whenever this
+                        // `poll_fn` future itself eventually is: resetting
+                        // a sibling `MaybeDone` to `Gone` drops its future
+                        // right now, which for cancel-safe uring ops
+                        // synchronously enqueues an `AsyncCancel` for that
+                        // op's in-flight submission.
+                        #( #siblings.as_mut().set(MaybeDone::Gone); )*
+                        return Ready(Err(__err));
+                    }
+                }
+            }
+        } else {
+            quote! {
+                #idx => {
+                    if #fut.as_mut().poll(cx).is_pending() {
+                        is_pending = true;
+                    }
+                }
+            }
+        }
+    });
+
+    let outputs = futs.iter().map(|fut| {
+        if try_mode {
+            quote! {
+                #fut
+                    .as_mut()
+                    .take_output()
+                    .expect("expected completed future")
+                    .ok()
+                    .expect("expected Ok(_)")
+            }
+        } else {
+            quote! {
+                #fut
+                    .as_mut()
+                    .take_output()
+                    .expect("expected completed future")
+            }
+        }
+    });
+
+    let ready = if try_mode {
+        quote! { Ready(Ok(( #( #outputs, )* ))) }
+    } else {
+        quote! { Ready(( #( #outputs, )* )) }
+    };
+
+    quote! {{
+        // Safety: nothing must be moved out of the `__fut_*` bindings below.
+        // This is to satisfy the requirement of `Pin::new_unchecked` called
+        // in the generated `poll_fn` closure.
+        #( #inits )*
+
+        // Rotated on every `poll_fn` invocation so that a branch which is
+        // always ready cannot starve the branches polled after it.
+        let mut __rotate = 0usize;
+
+        poll_fn(move |cx| {
+            const __BRANCHES: usize = #count;
+
+            #( #pins )*
+
+            // `% __BRANCHES` would panic at runtime on the first `poll` (not
+            // fail to compile) for a zero-branch `join!`/`try_join!`, so the
+            // degenerate case is guarded explicitly; the loop below never
+            // runs for it anyway.
+            let __start = if __BRANCHES == 0 { 0 } else { __rotate % __BRANCHES };
+            __rotate = __rotate.wrapping_add(1);
+
+            let mut is_pending = false;
+
+            for __i in 0..__BRANCHES {
+                let __branch = if __BRANCHES == 0 { 0 } else { (__start + __i) % __BRANCHES };
+                match __branch {
+                    #( #poll_arms )*
+                    _ => unreachable!(),
+                }
+            }
+
+            if is_pending {
+                Pending
+            } else {
+                #ready
+            }
+        })
+        .await
+    }}
+}
+
+/// Implementation of `monoio::join!`. Expects `maybe_done`, `poll_fn`,
+/// `Future`, `Pin`, `Poll::Ready` and `Poll::Pending` to already be in scope
+/// (the `monoio::join!` macro_rules wrapper brings them in via
+/// `$crate::macros::support` before invoking this).
+#[proc_macro]
+pub fn join_impl(input: TokenStream) -> TokenStream {
+    expand(input, false).into()
+}
+
+/// Implementation of `monoio::try_join!`. Same scope requirements as
+/// [`join_impl`], plus `MaybeDone` itself (used to reset a sibling branch to
+/// `MaybeDone::Gone` when cancelling it early).
+///
+/// On the first `Err`, the not-yet-`Ready` sibling branches are reset to
+/// `Gone` before this returns, dropping their inner futures synchronously.
+/// Branches passed to `try_join!` must therefore be cancel-safe uring ops:
+/// dropping one before it completes must be a valid way to abandon it.
+#[proc_macro]
+pub fn try_join_impl(input: TokenStream) -> TokenStream {
+    expand(input, true).into()
+}
@@ -0,0 +1,147 @@
+//! Future combinator that joins a runtime-sized collection of futures of
+//! the same type. See [`join_all`].
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use super::iter_pin_mut;
+use super::maybe_done::{maybe_done, MaybeDone};
+
+/// Future for the [`join_all`] function.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct JoinAll<F: Future> {
+    elems: Pin<Box<[MaybeDone<F>]>>,
+}
+
+/// Waits for a runtime-sized collection of futures of the same type to all
+/// complete.
+///
+/// `join!` requires the number of branches to be known at compile time and
+/// stores them inline in a tuple. `join_all` instead takes an
+/// [`IntoIterator`] of same-typed futures, storing them in a boxed slice, so
+/// the branch count can be decided at runtime (for example, one future per
+/// connection in a fan-out loop).
+///
+/// # Examples
+///
+/// There is no async runtime in this crate (yet), so this example polls the
+/// `join_all` future manually against a no-op [`Waker`](std::task::Waker)
+/// instead of using `#[monoio::main]`.
+///
+/// ```
+/// use std::future::Future as _;
+/// use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+///
+/// async fn double(i: u32) -> u32 {
+///     i * 2
+/// }
+///
+/// fn noop_waker() -> Waker {
+///     fn noop(_: *const ()) {}
+///     fn clone(_: *const ()) -> RawWaker {
+///         raw_waker()
+///     }
+///     static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+///     fn raw_waker() -> RawWaker {
+///         RawWaker::new(std::ptr::null(), &VTABLE)
+///     }
+///     unsafe { Waker::from_raw(raw_waker()) }
+/// }
+///
+/// let mut fut = Box::pin(monoio::join_all((0..3).map(double)));
+///
+/// let waker = noop_waker();
+/// let mut cx = Context::from_waker(&waker);
+/// assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(vec![0, 2, 4]));
+/// ```
+pub fn join_all<I>(iter: I) -> JoinAll<I::Item>
+where
+    I: IntoIterator,
+    I::Item: Future,
+{
+    let elems: Box<[_]> = iter.into_iter().map(maybe_done).collect();
+    JoinAll {
+        elems: elems.into(),
+    }
+}
+
+impl<F: Future> Future for JoinAll<F> {
+    type Output = Vec<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut all_done = true;
+
+        for elem in iter_pin_mut(self.elems.as_mut()) {
+            if elem.poll(cx).is_pending() {
+                all_done = false;
+            }
+        }
+
+        if !all_done {
+            return Poll::Pending;
+        }
+
+        let elems = &mut self.elems;
+        let result = iter_pin_mut(elems.as_mut())
+            .map(|elem| elem.take_output().expect("expected completed future"))
+            .collect();
+        Poll::Ready(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use crate::test_util::noop_waker;
+
+    use super::join_all;
+
+    /// Resolves with `value` after being polled `polls_before_ready` more
+    /// times, so tests can mix branches that finish on different polls.
+    struct ReadyAfter {
+        value: u32,
+        polls_before_ready: usize,
+    }
+
+    impl Future for ReadyAfter {
+        type Output = u32;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+            if self.polls_before_ready == 0 {
+                return Poll::Ready(self.value);
+            }
+            self.polls_before_ready -= 1;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn resolves_all_branches_preserving_order() {
+        let branches = vec![
+            ReadyAfter { value: 10, polls_before_ready: 2 },
+            ReadyAfter { value: 20, polls_before_ready: 0 },
+            ReadyAfter { value: 30, polls_before_ready: 1 },
+        ];
+        let mut fut = Box::pin(join_all(branches));
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut result = None;
+        for _ in 0..3 {
+            if let Poll::Ready(values) = fut.as_mut().poll(&mut cx) {
+                result = Some(values);
+                break;
+            }
+        }
+
+        // Branch 0 takes the longest to resolve, but the output is still in
+        // the branches' original declaration order, not completion order.
+        assert_eq!(result, Some(vec![10, 20, 30]));
+    }
+}
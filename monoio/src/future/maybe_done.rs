@@ -0,0 +1,100 @@
+//! Definition of the `MaybeDone` combinator.
+
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A future that may have completed.
+///
+/// This is the building block used by the [`join!`]/[`try_join!`] macros
+/// (and the [`join_all`]/[`try_join_all`] combinators) to poll a set of
+/// futures to completion without re-polling a future that has already
+/// produced its output.
+///
+/// [`join!`]: crate::join
+/// [`try_join!`]: crate::try_join
+/// [`join_all`]: crate::join_all
+/// [`try_join_all`]: crate::try_join_all
+#[derive(Debug)]
+pub enum MaybeDone<Fut: Future> {
+    /// A not-yet-completed future.
+    Future(Fut),
+    /// The output of the completed future.
+    Done(Fut::Output),
+    /// The empty variant after the result of a [`MaybeDone`] has been taken
+    /// using the [`take_output`](MaybeDone::take_output) method.
+    Gone,
+}
+
+// Safe because we never generate `Pin<&mut Fut::Output>`.
+impl<Fut: Future + Unpin> Unpin for MaybeDone<Fut> {}
+
+/// Wraps a future into a `MaybeDone`.
+pub fn maybe_done<Fut: Future>(future: Fut) -> MaybeDone<Fut> {
+    MaybeDone::Future(future)
+}
+
+impl<Fut: Future> MaybeDone<Fut> {
+    /// Returns a mutable reference to the output of the future, if it has
+    /// completed and [`take_output`](MaybeDone::take_output) has not yet
+    /// been called.
+    ///
+    /// `pub` (rather than `pub(crate)`) because the `join!`/`try_join!`
+    /// macros expand into proc-macro-generated code that runs in the
+    /// *caller's* crate, which needs to call this method on the `MaybeDone`
+    /// values the macro creates.
+    #[inline]
+    pub fn output_mut(self: Pin<&mut Self>) -> Option<&mut Fut::Output> {
+        // Safety: we only ever hand out `&mut Fut::Output`, never moving the
+        // `Fut` variant, so this upholds the pin invariant for `Fut`.
+        unsafe {
+            match self.get_unchecked_mut() {
+                MaybeDone::Done(res) => Some(res),
+                _ => None,
+            }
+        }
+    }
+
+    /// Attempts to take the output of a `MaybeDone` without driving it
+    /// towards completion.
+    ///
+    /// See [`output_mut`](MaybeDone::output_mut) for why this is `pub`.
+    #[inline]
+    pub fn take_output(self: Pin<&mut Self>) -> Option<Fut::Output> {
+        match &*self {
+            MaybeDone::Done(_) => {}
+            MaybeDone::Future(_) | MaybeDone::Gone => return None,
+        }
+        // Safety: we immediately replace `self` with `Gone`, so the `Fut`
+        // variant (which may not be `Unpin`) is never moved.
+        unsafe {
+            match mem::replace(self.get_unchecked_mut(), MaybeDone::Gone) {
+                MaybeDone::Done(output) => Some(output),
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<Fut: Future> Future for MaybeDone<Fut> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        // Safety: we only project to the inner `Fut` to poll it, and only
+        // replace `self` with `Done` after that future has itself resolved,
+        // which does not move anything out of a `Pin`.
+        unsafe {
+            let res = match self.as_mut().get_unchecked_mut() {
+                MaybeDone::Future(fut) => match Pin::new_unchecked(fut).poll(cx) {
+                    Poll::Ready(res) => res,
+                    Poll::Pending => return Poll::Pending,
+                },
+                MaybeDone::Done(_) => return Poll::Ready(()),
+                MaybeDone::Gone => panic!("MaybeDone polled after value taken"),
+            };
+            self.set(MaybeDone::Done(res));
+        }
+        Poll::Ready(())
+    }
+}
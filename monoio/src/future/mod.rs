@@ -0,0 +1,27 @@
+//! Future combinators used throughout monoio.
+
+pub(crate) mod maybe_done;
+#[cfg(feature = "macros")]
+pub(crate) mod poll_fn;
+
+mod join_all;
+mod try_join_all;
+
+pub use join_all::{join_all, JoinAll};
+pub use try_join_all::{try_join_all, TryJoinAll};
+
+use std::pin::Pin;
+
+/// Turns a `Pin<&mut [T]>` into an iterator of `Pin<&mut T>`.
+///
+/// Shared by [`join_all`] and [`try_join_all`], which both hold their
+/// branches in a single pinned boxed slice (rather than a tuple, as
+/// `join!`/`try_join!` do) since the branch count is only known at runtime.
+pub(crate) fn iter_pin_mut<T>(slice: Pin<&mut [T]>) -> impl Iterator<Item = Pin<&mut T>> {
+    // Safety: `slice` is already pinned, and we only ever hand out
+    // `Pin<&mut T>` for each element, so a caller cannot move an element out
+    // without unsafe code of their own.
+    unsafe { slice.get_unchecked_mut() }
+        .iter_mut()
+        .map(|t| unsafe { Pin::new_unchecked(t) })
+}
@@ -0,0 +1,197 @@
+//! Future combinator that joins a runtime-sized collection of fallible
+//! futures of the same type, short-circuiting on the first `Err`. See
+//! [`try_join_all`].
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use super::iter_pin_mut;
+use super::maybe_done::{maybe_done, MaybeDone};
+
+/// Future for the [`try_join_all`] function.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct TryJoinAll<F: Future> {
+    elems: Pin<Box<[MaybeDone<F>]>>,
+}
+
+/// Waits for a runtime-sized collection of fallible futures of the same
+/// type to all complete with `Ok(_)`, or short-circuits on the first
+/// `Err(_)`.
+///
+/// This is the dynamic-collection counterpart to [`try_join!`](crate::try_join):
+/// where `try_join!` requires the branch count to be known at compile time
+/// and stores branches inline in a tuple, `try_join_all` takes an
+/// [`IntoIterator`] of same-typed futures, so the branch count can be
+/// decided at runtime (for example, one future per connection in a fan-out
+/// loop).
+///
+/// ### Cancellation
+///
+/// As with [`try_join!`](crate::try_join), when one branch returns `Err`
+/// the other branches are not left to be dropped whenever `self` eventually
+/// is: they are reset to [`MaybeDone::Gone`] right away, dropping their
+/// futures before the error is returned. Branches passed to `try_join_all`
+/// must be cancel-safe uring ops for the same reason.
+///
+/// # Examples
+///
+/// There is no async runtime in this crate (yet), so this example polls the
+/// `try_join_all` future manually against a no-op [`Waker`](std::task::Waker)
+/// instead of using `#[monoio::main]`.
+///
+/// ```
+/// use std::future::Future as _;
+/// use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+///
+/// async fn double(i: u32) -> Result<u32, &'static str> {
+///     Ok(i * 2)
+/// }
+///
+/// fn noop_waker() -> Waker {
+///     fn noop(_: *const ()) {}
+///     fn clone(_: *const ()) -> RawWaker {
+///         raw_waker()
+///     }
+///     static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+///     fn raw_waker() -> RawWaker {
+///         RawWaker::new(std::ptr::null(), &VTABLE)
+///     }
+///     unsafe { Waker::from_raw(raw_waker()) }
+/// }
+///
+/// let mut fut = Box::pin(monoio::try_join_all((0..3).map(double)));
+///
+/// let waker = noop_waker();
+/// let mut cx = Context::from_waker(&waker);
+/// assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(Ok(vec![0, 2, 4])));
+/// ```
+pub fn try_join_all<I, T, E>(iter: I) -> TryJoinAll<I::Item>
+where
+    I: IntoIterator,
+    I::Item: Future<Output = Result<T, E>>,
+{
+    let elems: Box<[_]> = iter.into_iter().map(maybe_done).collect();
+    TryJoinAll {
+        elems: elems.into(),
+    }
+}
+
+impl<T, E, F: Future<Output = Result<T, E>>> Future for TryJoinAll<F> {
+    type Output = Result<Vec<T>, E>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut all_done = true;
+        let mut err_index = None;
+
+        for (i, mut elem) in iter_pin_mut(self.elems.as_mut()).enumerate() {
+            if elem.as_mut().poll(cx).is_pending() {
+                all_done = false;
+            } else if elem
+                .as_mut()
+                .output_mut()
+                .expect("expected completed future")
+                .is_err()
+            {
+                err_index = Some(i);
+                break;
+            }
+        }
+
+        if let Some(i) = err_index {
+            let err = iter_pin_mut(self.elems.as_mut())
+                .nth(i)
+                .unwrap()
+                .take_output()
+                .expect("expected completed future")
+                .err()
+                .unwrap();
+
+            // Eagerly cancel every other branch instead of leaving them to
+            // be dropped whenever `self` (and so the whole boxed slice)
+            // eventually is.
+            for (j, mut elem) in iter_pin_mut(self.elems.as_mut()).enumerate() {
+                if j != i {
+                    elem.as_mut().set(MaybeDone::Gone);
+                }
+            }
+
+            return Poll::Ready(Err(err));
+        }
+
+        if !all_done {
+            return Poll::Pending;
+        }
+
+        let elems = &mut self.elems;
+        let result = iter_pin_mut(elems.as_mut())
+            .map(|mut elem| {
+                elem.as_mut()
+                    .take_output()
+                    .expect("expected completed future")
+                    .ok()
+                    .expect("expected Ok(_)")
+            })
+            .collect();
+        Poll::Ready(Ok(result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::rc::Rc;
+    use std::task::{Context, Poll};
+
+    use crate::test_util::{noop_waker, DropMark};
+
+    use super::try_join_all;
+
+    enum Behavior {
+        // Held only for its `Drop` side effect; never read.
+        NeverReady(#[allow(dead_code)] DropMark),
+        FailsImmediately,
+    }
+
+    struct Branch(Behavior);
+
+    impl Future for Branch {
+        type Output = Result<(), &'static str>;
+
+        fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+            match self.get_mut().0 {
+                Behavior::NeverReady(_) => Poll::Pending,
+                Behavior::FailsImmediately => Poll::Ready(Err("boom")),
+            }
+        }
+    }
+
+    #[test]
+    fn cancels_sibling_branches_as_soon_as_one_fails() {
+        let dropped = Rc::new(RefCell::new(Vec::new()));
+
+        let branches = vec![
+            Branch(Behavior::NeverReady(DropMark { name: "a", dropped: dropped.clone() })),
+            Branch(Behavior::FailsImmediately),
+            Branch(Behavior::NeverReady(DropMark { name: "b", dropped: dropped.clone() })),
+        ];
+        let mut fut = Box::pin(try_join_all(branches));
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        match fut.as_mut().poll(&mut cx) {
+            Poll::Ready(Err("boom")) => {}
+            other => panic!("expected an immediate Err(\"boom\"), got {other:?}"),
+        }
+
+        // The other two branches must already have been dropped as part of
+        // resolving this one `poll` call, not whenever `fut` itself is
+        // eventually dropped.
+        let mut dropped = dropped.borrow().clone();
+        dropped.sort_unstable();
+        assert_eq!(dropped, vec!["a", "b"]);
+    }
+}
@@ -0,0 +1,16 @@
+//! monoio is a thread-per-core io_uring runtime for Rust.
+
+#[doc(hidden)]
+#[cfg(feature = "macros")]
+pub use monoio_macros::{join_impl, try_join_impl};
+
+#[cfg(feature = "macros")]
+#[macro_use]
+pub mod macros;
+
+pub mod future;
+
+pub use future::{join_all, try_join_all};
+
+#[cfg(test)]
+mod test_util;
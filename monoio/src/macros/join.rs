@@ -0,0 +1,152 @@
+/// Wait on multiple concurrent branches, returning when **all** branches
+/// complete.
+///
+/// The `join!` macro must be used inside of async functions, closures, and
+/// blocks.
+///
+/// The `join!` macro takes a list of async expressions and evaluates them
+/// concurrently on the same task. Each async expression evaluates to a
+/// future and the futures from each expression are multiplexed on the
+/// current task. The `join!` macro returns when **all** branches complete.
+///
+/// For waiting on a single future to complete, it is recommended to simply
+/// use `.await` on the future. For branches that return `Result` and should
+/// stop at the first `Err`, see the [`try_join!`] macro.
+///
+/// [`try_join!`]: macro@try_join
+///
+/// # Notes
+///
+/// The supplied futures are stored inline and does not require allocating a
+/// `Vec`.
+///
+/// ### Runtime characteristics
+///
+/// By running all async expressions on the current task, the expressions are
+/// able to run **concurrently** but not in **parallel**. This means all
+/// expressions are run on the same thread and if one branch blocks the thread,
+/// all other expressions will be unable to continue. This crate has no
+/// `spawn` function, so `join!` offers no way to run branches in parallel.
+///
+/// ### Implementation
+///
+/// `join!` is a thin `macro_rules!` wrapper around a proc macro in
+/// `monoio-macros`. The wrapper brings `maybe_done`, `poll_fn`, `Future`,
+/// `Pin` and `Poll` into scope from `$crate::macros::support` (so the
+/// `$crate`-qualified surface callers rely on is unchanged); the proc macro
+/// itself emits the branch-polling `poll_fn` body. See the `monoio-macros`
+/// crate documentation for why it's a proc macro and for the fairness
+/// rotation this generates.
+///
+/// # Examples
+///
+/// Basic join with two branches.
+///
+/// There is no async runtime in this crate (yet), so this example polls the
+/// `join!` future manually against a no-op [`Waker`](std::task::Waker)
+/// instead of using `#[monoio::main]`.
+///
+/// ```
+/// use std::future::Future as _;
+/// use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+///
+/// async fn do_stuff_async() {
+///     // async work
+/// }
+///
+/// async fn more_async_work() {
+///     // more here
+/// }
+///
+/// fn noop_waker() -> Waker {
+///     fn noop(_: *const ()) {}
+///     fn clone(_: *const ()) -> RawWaker {
+///         raw_waker()
+///     }
+///     static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+///     fn raw_waker() -> RawWaker {
+///         RawWaker::new(std::ptr::null(), &VTABLE)
+///     }
+///     unsafe { Waker::from_raw(raw_waker()) }
+/// }
+///
+/// let fut = async {
+///     let (first, second) = monoio::join!(
+///         do_stuff_async(),
+///         more_async_work());
+///
+///     // do something with the values
+/// };
+/// let mut fut = Box::pin(fut);
+///
+/// let waker = noop_waker();
+/// let mut cx = Context::from_waker(&waker);
+/// assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(()));
+/// ```
+#[macro_export]
+#[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
+macro_rules! join {
+    ($($e:expr),* $(,)?) => {{
+        use $crate::macros::support::{maybe_done, poll_fn, Future, Pin};
+        use $crate::macros::support::Poll::{Ready, Pending};
+
+        $crate::join_impl!($($e),*)
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::rc::Rc;
+    use std::task::{Context, Poll};
+
+    use crate::test_util::noop_waker;
+
+    /// Records its own branch index on every poll, and never completes, so a
+    /// surrounding `join!` keeps re-polling it on every call.
+    struct Track {
+        idx: usize,
+        visits: Rc<RefCell<Vec<usize>>>,
+    }
+
+    impl Future for Track {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+            let this = self.get_mut();
+            this.visits.borrow_mut().push(this.idx);
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn rotates_which_branch_is_polled_first() {
+        let visits = Rc::new(RefCell::new(Vec::new()));
+        let fut = async {
+            join!(
+                Track { idx: 0, visits: visits.clone() },
+                Track { idx: 1, visits: visits.clone() },
+                Track { idx: 2, visits: visits.clone() },
+            )
+        };
+        let mut fut = Box::pin(fut);
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut starts = Vec::new();
+        for _ in 0..4 {
+            visits.borrow_mut().clear();
+            assert!(fut.as_mut().poll(&mut cx).is_pending());
+            let round = visits.borrow().clone();
+            assert_eq!(round.len(), 3, "every branch should be polled once per round");
+            starts.push(round[0]);
+        }
+
+        // With 3 branches, the starting index advances by one (mod 3) each
+        // time the `join!` future is polled, instead of always starting at 0.
+        assert_eq!(starts, vec![0, 1, 2, 0]);
+    }
+}
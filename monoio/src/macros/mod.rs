@@ -0,0 +1,7 @@
+//! Macros for monoio.
+
+mod join;
+mod try_join;
+
+#[doc(hidden)]
+pub mod support;
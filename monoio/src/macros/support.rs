@@ -0,0 +1,13 @@
+//! Re-exports used by the `join!`/`try_join!` macros, and by the
+//! `monoio-macros` proc macros that back them.
+//!
+//! This indirection means the macros (and the proc-macro crate) only ever
+//! reach into `$crate::macros::support`, rather than `std::future`,
+//! `std::task`, or this crate's internal future combinators directly, so
+//! those internals can move around without breaking the macros.
+
+pub use crate::future::maybe_done::{maybe_done, MaybeDone};
+pub use crate::future::poll_fn::poll_fn;
+pub use std::future::Future;
+pub use std::pin::Pin;
+pub use std::task::Poll;
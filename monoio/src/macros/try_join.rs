@@ -28,11 +28,30 @@
 ///
 /// [`monoio::spawn`]: crate::spawn
 ///
+/// ### Implementation
+///
+/// `try_join!` is a thin `macro_rules!` wrapper around a proc macro in
+/// `monoio-macros`. The wrapper brings `maybe_done`, `poll_fn`, `Future`,
+/// `Pin` and `Poll` into scope from `$crate::macros::support` (so the
+/// `$crate`-qualified surface callers rely on is unchanged); the proc macro
+/// itself emits the branch-polling `poll_fn` body, including the fairness
+/// rotation and the eager sibling-cancellation-on-`Err` described in the
+/// `monoio-macros` crate documentation. Branches passed to `try_join!` must
+/// be cancel-safe for that reason: dropping one before it completes must be
+/// a valid way to abandon it.
+///
 /// # Examples
 ///
 /// Basic try_join with two branches.
 ///
+/// There is no async runtime in this crate (yet), so this example polls the
+/// `try_join!` future manually against a no-op [`Waker`](std::task::Waker)
+/// instead of using `#[monoio::main]`.
+///
 /// ```
+/// use std::future::Future as _;
+/// use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+///
 /// async fn do_stuff_async() -> Result<(), &'static str> {
 ///     // async work
 /// # Ok(())
@@ -43,90 +62,157 @@
 /// # Ok(())
 /// }
 ///
-/// #[monoio::main]
-/// async fn main() {
-///     let res = monoio::try_join!(
+/// fn noop_waker() -> Waker {
+///     fn noop(_: *const ()) {}
+///     fn clone(_: *const ()) -> RawWaker {
+///         raw_waker()
+///     }
+///     static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+///     fn raw_waker() -> RawWaker {
+///         RawWaker::new(std::ptr::null(), &VTABLE)
+///     }
+///     unsafe { Waker::from_raw(raw_waker()) }
+/// }
+///
+/// let fut = async {
+///     monoio::try_join!(
 ///         do_stuff_async(),
-///         more_async_work());
-///
-///     match res {
-///          Ok((first, second)) => {
-///              // do something with the values
-///          }
-///          Err(err) => {
-///             println!("processing failed; error = {}", err);
-///          }
+///         more_async_work())
+/// };
+/// let mut fut = Box::pin(fut);
+///
+/// let waker = noop_waker();
+/// let mut cx = Context::from_waker(&waker);
+/// match fut.as_mut().poll(&mut cx) {
+///     Poll::Ready(Ok((first, second))) => {
+///         // do something with the values
+///     }
+///     Poll::Ready(Err(err)) => {
+///         println!("processing failed; error = {}", err);
 ///     }
+///     Poll::Pending => unreachable!(),
 /// }
 /// ```
 #[macro_export]
 #[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
 macro_rules! try_join {
-    (@ {
-        // One `_` for each branch in the `try_join!` macro. This is not used once
-        // normalization is complete.
-        ( $($count:tt)* )
-
-        // Normalized try_join! branches
-        $( ( $($skip:tt)* ) $e:expr, )*
-
-    }) => {{
-        use $crate::macros::support::{maybe_done, poll_fn, Future, Pin};
+    ($($e:expr),* $(,)?) => {{
+        use $crate::macros::support::{maybe_done, poll_fn, Future, MaybeDone, Pin};
         use $crate::macros::support::Poll::{Ready, Pending};
 
-        // Safety: nothing must be moved out of `futures`. This is to satisfy
-        // the requirement of `Pin::new_unchecked` called below.
-        let mut futures = ( $( maybe_done($e), )* );
-
-        poll_fn(move |cx| {
-            let mut is_pending = false;
-
-            $(
-                // Extract the future for this branch from the tuple.
-                let ( $($skip,)* fut, .. ) = &mut futures;
-
-                // Safety: future is stored on the stack above
-                // and never moved.
-                let mut fut = unsafe { Pin::new_unchecked(fut) };
-
-                // Try polling
-                if fut.as_mut().poll(cx).is_pending() {
-                    is_pending = true;
-                } else if fut.as_mut().output_mut().expect("expected completed future").is_err() {
-                    return Ready(Err(fut.take_output().expect("expected completed future").err().unwrap()))
-                }
-            )*
-
-            if is_pending {
-                Pending
-            } else {
-                Ready(Ok(($({
-                    // Extract the future for this branch from the tuple.
-                    let ( $($skip,)* fut, .. ) = &mut futures;
-
-                    // Safety: future is stored on the stack above
-                    // and never moved.
-                    let mut fut = unsafe { Pin::new_unchecked(fut) };
-
-                    fut
-                        .take_output()
-                        .expect("expected completed future")
-                        .ok()
-                        .expect("expected Ok(_)")
-                },)*)))
-            }
-        }).await
+        $crate::try_join_impl!($($e),*)
     }};
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::rc::Rc;
+    use std::task::{Context, Poll};
+
+    use crate::test_util::{noop_waker, DropMark};
+
+    struct NeverReady {
+        _mark: DropMark,
+    }
+
+    impl Future for NeverReady {
+        type Output = Result<(), &'static str>;
+
+        fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+            Poll::Pending
+        }
+    }
+
+    struct FailsImmediately;
+
+    impl Future for FailsImmediately {
+        type Output = Result<(), &'static str>;
+
+        fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+            Poll::Ready(Err("boom"))
+        }
+    }
+
+    /// Records its own branch index on every poll, and never completes, so a
+    /// surrounding `try_join!` keeps re-polling it on every call.
+    struct Track {
+        idx: usize,
+        visits: Rc<RefCell<Vec<usize>>>,
+    }
+
+    impl Future for Track {
+        type Output = Result<(), &'static str>;
+
+        fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let this = self.get_mut();
+            this.visits.borrow_mut().push(this.idx);
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn rotates_which_branch_is_polled_first() {
+        let visits = Rc::new(RefCell::new(Vec::new()));
+        let fut = async {
+            try_join!(
+                Track { idx: 0, visits: visits.clone() },
+                Track { idx: 1, visits: visits.clone() },
+                Track { idx: 2, visits: visits.clone() },
+            )
+        };
+        let mut fut = Box::pin(fut);
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut starts = Vec::new();
+        for _ in 0..4 {
+            visits.borrow_mut().clear();
+            assert!(fut.as_mut().poll(&mut cx).is_pending());
+            let round = visits.borrow().clone();
+            assert_eq!(round.len(), 3, "every branch should be polled once per round");
+            starts.push(round[0]);
+        }
+
+        // With 3 branches, the starting index advances by one (mod 3) each
+        // time the `try_join!` future is polled, instead of always starting
+        // at 0.
+        assert_eq!(starts, vec![0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn cancels_sibling_branches_as_soon_as_one_fails() {
+        let dropped = Rc::new(RefCell::new(Vec::new()));
 
-    // ===== Normalize =====
+        let fut = async {
+            try_join!(
+                NeverReady {
+                    _mark: DropMark { name: "a", dropped: dropped.clone() },
+                },
+                FailsImmediately,
+                NeverReady {
+                    _mark: DropMark { name: "b", dropped: dropped.clone() },
+                },
+            )
+        };
+        let mut fut = Box::pin(fut);
 
-    (@ { ( $($s:tt)* ) $($t:tt)* } $e:expr, $($r:tt)* ) => {
-        $crate::try_join!(@{ ($($s)* _) $($t)* ($($s)*) $e, } $($r)*)
-    };
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
 
-    // ===== Entry point =====
+        match fut.as_mut().poll(&mut cx) {
+            Poll::Ready(Err("boom")) => {}
+            other => panic!("expected an immediate Err(\"boom\"), got {other:?}"),
+        }
 
-    ( $($e:expr),* $(,)?) => {
-        $crate::try_join!(@{ () } $($e,)*)
-    };
+        // The other two branches must already have been dropped as part of
+        // resolving this one `poll` call, not whenever `fut` itself is
+        // eventually dropped.
+        let mut dropped = dropped.borrow().clone();
+        dropped.sort_unstable();
+        assert_eq!(dropped, vec!["a", "b"]);
+    }
 }
@@ -0,0 +1,43 @@
+//! Test-only helpers shared across this crate's unit tests.
+//!
+//! There is no async runtime in this crate (yet) to drive futures with, so
+//! unit tests for combinators poll them manually against a no-op [`Waker`].
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::task::{RawWaker, RawWakerVTable, Waker};
+
+fn noop(_: *const ()) {}
+
+fn clone(_: *const ()) -> RawWaker {
+    raw_waker()
+}
+
+static NOOP_WAKER_VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+fn raw_waker() -> RawWaker {
+    RawWaker::new(std::ptr::null(), &NOOP_WAKER_VTABLE)
+}
+
+/// A [`Waker`] that does nothing when woken.
+///
+/// Good enough for tests that poll a future in a tight loop themselves and
+/// don't need to be woken back up by it.
+pub(crate) fn noop_waker() -> Waker {
+    // Safety: `NOOP_WAKER_VTABLE`'s functions satisfy the `RawWaker`/`RawWakerVTable` contract
+    // (ignoring the data pointer entirely, so there's nothing for clone/drop to manage).
+    unsafe { Waker::from_raw(raw_waker()) }
+}
+
+/// Pushes its `name` onto a shared list when dropped, so tests can tell whether a
+/// branch's future was cancelled rather than merely left pending.
+pub(crate) struct DropMark {
+    pub(crate) name: &'static str,
+    pub(crate) dropped: Rc<RefCell<Vec<&'static str>>>,
+}
+
+impl Drop for DropMark {
+    fn drop(&mut self) {
+        self.dropped.borrow_mut().push(self.name);
+    }
+}